@@ -0,0 +1,105 @@
+// Copyright (c) 2020 Google LLC All rights reserved.
+// Use of this source code is governed by a BSD-style
+// license that can be found in the LICENSE file.
+
+//! `argh` is a derive-based argument parser. Annotate a struct with
+//! `#[derive(FromArgs)]` and `#[argh(...)]` field attributes to generate a
+//! `--help`/`--help-json`/`--help-completion`-aware command-line parser.
+
+pub use argh_derive::FromArgs;
+
+/// Information about a subcommand, used to print `--help`/`--help-json`
+/// output.
+pub struct CommandInfo {
+    /// The name used to select this subcommand on the command line.
+    pub name: &'static str,
+    /// A short description of the subcommand's functionality.
+    pub description: &'static str,
+}
+
+/// Implemented by the enum a `#[argh(subcommand)]` field holds, listing the
+/// subcommands available for `--help` and `--help-json`.
+pub trait SubCommands {
+    /// The subcommands available, in declaration order.
+    const COMMANDS: &'static [&'static CommandInfo];
+}
+
+/// Implemented by every `#[derive(FromArgs)]` type: exposes its own
+/// flags/positionals, and (for a `#[argh(subcommand)]` enum) its
+/// subcommands' own info in turn, so `--help-completion` can build a
+/// completion script that recurses into a selected subcommand's option set.
+pub trait Completion {
+    /// This type's flags, positionals, and subcommand tree.
+    const COMPLETION_INFO: argh_shared::CompletionInfo;
+}
+
+/// Implemented by every `#[derive(FromArgs)]` type: parses `args` (with
+/// `command_name` as the already-consumed leading words, e.g. `["prog",
+/// "subcmd"]`) into `Self`, or requests an early exit (for `--help` and
+/// parse errors).
+pub trait FromArgs: Sized {
+    /// Parses `args` into `Self`.
+    fn from_args(command_name: &[&str], args: &[&str]) -> Result<Self, EarlyExit>;
+}
+
+/// Returned by [`FromArgs::from_args`] when parsing should stop early: either
+/// because help was requested (`status` is `Ok(())`) or because an error was
+/// encountered (`status` is `Err(())`).
+#[derive(Debug)]
+pub struct EarlyExit {
+    /// The text to print (to stdout for help, stderr for errors).
+    pub output: String,
+    /// `Ok(())` for a help request, `Err(())` for a parse error.
+    pub status: Result<(), ()>,
+}
+
+impl EarlyExit {
+    /// A successful early exit (e.g. `--help`) that prints `output`.
+    pub fn from_output(output: String) -> Self {
+        EarlyExit { output, status: Ok(()) }
+    }
+
+    /// A parse error that prints `message` as the failure reason.
+    pub fn from_message(message: String) -> Self {
+        EarlyExit { output: format!("Error: {message}\n"), status: Err(()) }
+    }
+}
+
+/// Renders a `--help` "Commands:" section from `commands`: each name is
+/// styled as a flag name, as in the "Options:" section, and the section is
+/// laid out by `argh_shared::wrap_section`, like the "Options:" and
+/// "Positional Arguments:" sections.
+pub fn print_subcommands(commands: &[&CommandInfo]) -> String {
+    let styled_names: Vec<String> =
+        commands.iter().map(|c| argh_shared::style(argh_shared::TAG_FLAG_NAME, c.name)).collect();
+    let entries: Vec<(&str, &str)> = commands
+        .iter()
+        .zip(&styled_names)
+        .map(|(c, styled_name)| (styled_name.as_str(), c.description))
+        .collect();
+    argh_shared::wrap_section(&entries)
+}
+
+/// Parses a `T` from the real process's command-line arguments
+/// (`std::env::args()`), printing `--help` output or a parse error and
+/// exiting the process as appropriate.
+pub fn from_env<T: FromArgs>() -> T {
+    let strings: Vec<String> = std::env::args().collect();
+    let command_name = [strings.first().map(String::as_str).unwrap_or("")];
+    let args: Vec<&str> = strings.iter().skip(1).map(String::as_str).collect();
+    match T::from_args(&command_name, &args) {
+        Ok(value) => value,
+        Err(early_exit) => {
+            match early_exit.status {
+                Ok(()) => {
+                    print!("{}", early_exit.output);
+                    std::process::exit(0);
+                }
+                Err(()) => {
+                    eprint!("{}", early_exit.output);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
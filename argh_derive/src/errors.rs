@@ -0,0 +1,37 @@
+// Copyright (c) 2020 Google LLC All rights reserved.
+// Use of this source code is governed by a BSD-style
+// license that can be found in the LICENSE file.
+
+use {
+    proc_macro2::{Span, TokenStream},
+    quote::ToTokens,
+    std::cell::RefCell,
+};
+
+/// Accumulates `syn::Error`s encountered while expanding the derive macro so
+/// that a single invocation can report every problem it finds instead of
+/// bailing out on the first one.
+#[derive(Default)]
+pub struct Errors {
+    errors: RefCell<Vec<syn::Error>>,
+}
+
+impl Errors {
+    /// Records an error at `span` with message `msg`.
+    pub fn err_span(&self, span: Span, msg: &str) {
+        self.errors.borrow_mut().push(syn::Error::new(span, msg));
+    }
+
+    /// Records `err`.
+    pub fn push(&self, err: syn::Error) {
+        self.errors.borrow_mut().push(err);
+    }
+}
+
+impl ToTokens for Errors {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for error in self.errors.borrow().iter() {
+            tokens.extend(error.to_compile_error());
+        }
+    }
+}
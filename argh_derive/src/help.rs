@@ -5,7 +5,7 @@
 use {
     crate::{
         errors::Errors,
-        parse_attrs::{Description, FieldKind, TypeAttrs},
+        parse_attrs::{Description, FieldKind, TypeAttrs, ValueHint},
         Optionality, StructField,
     },
     argh_shared::INDENT,
@@ -20,9 +20,44 @@ const HELP_FLAG: &str = "--help";
 const HELP_DESCRIPTION: &str = "display usage information";
 const HELP_JSON_FLAG: &str = "--help-json";
 const HELP_JSON_DESCRIPTION: &str = "display usage information encoded in JSON";
+const HELP_COMPLETION_FLAG: &str = "--help-completion";
+const HELP_COMPLETION_DESCRIPTION: &str = "generate a shell completion script for <shell> (bash, zsh, fish)";
+
+/// The role a span of help text plays, used to pick its color/weight when
+/// `argh_shared::colorize` renders with color enabled.
+#[derive(Clone, Copy)]
+enum Style {
+    /// A section heading, e.g. "Options:".
+    Heading,
+    /// A flag name, e.g. "-f, --force".
+    FlagName,
+    /// A placeholder, e.g. "<file>".
+    Placeholder,
+}
+
+impl Style {
+    fn tag(self) -> char {
+        match self {
+            Style::Heading => argh_shared::TAG_HEADING,
+            Style::FlagName => argh_shared::TAG_FLAG_NAME,
+            Style::Placeholder => argh_shared::TAG_PLACEHOLDER,
+        }
+    }
+}
+
+/// Wraps `text` in the control characters that mark it as `style` for
+/// `argh_shared::colorize`.
+fn styled(style: Style, text: &str) -> String {
+    argh_shared::style(style.tag(), text)
+}
 
 /// Returns a `TokenStream` generating a `String` help message.
 ///
+/// Headings, flag names, and placeholders are wrapped in style markers (see
+/// [`styled`]); `argh_shared::colorize` renders those at help-print time.
+/// Positional and option sections are wrapped to the terminal width at that
+/// same point, via `argh_shared::wrap_section`.
+///
 /// Note: `fields` entries with `is_subcommand.is_some()` will be ignored
 /// in favor of the `subcommand` argument.
 pub(crate) fn help(
@@ -32,7 +67,7 @@ pub(crate) fn help(
     fields: &[StructField<'_>],
     subcommand: Option<&StructField<'_>>,
 ) -> TokenStream {
-    let mut format_lit = "Usage: {command_name}".to_string();
+    let mut format_lit = format!("{} {{command_name}}", styled(Style::Heading, "Usage:"));
 
     build_usage_command_line(&mut format_lit, fields, subcommand);
 
@@ -41,33 +76,56 @@ pub(crate) fn help(
     let description = require_description(errors, Span::call_site(), &ty_attrs.description, "type");
     format_lit.push_str(&description);
 
-    let mut positional = fields.iter().filter(|f| f.kind == FieldKind::Positional).peekable();
+    let positional_args: Vec<&StructField<'_>> =
+        fields.iter().filter(|f| f.kind == FieldKind::Positional).collect();
 
-    if positional.peek().is_some() {
+    let positional_section_calculation;
+    let positional_section_format_arg;
+    if !positional_args.is_empty() {
         format_lit.push_str(SECTION_SEPARATOR);
-        format_lit.push_str("Positional Arguments:");
-        for arg in positional {
-            positional_description(&mut format_lit, arg);
-        }
+        format_lit.push_str(&styled(Style::Heading, "Positional Arguments:"));
+        format_lit.push_str("{positional_section}");
+
+        let (names, descriptions): (Vec<String>, Vec<String>) =
+            positional_args.iter().map(|arg| positional_help_pair(arg)).unzip();
+        positional_section_calculation = quote! {
+            let positional_section =
+                argh_shared::wrap_section(&[#( (#names, #descriptions) ),*]);
+        };
+        positional_section_format_arg = quote! { , positional_section = positional_section };
+    } else {
+        positional_section_calculation = TokenStream::new();
+        positional_section_format_arg = TokenStream::new();
     }
 
     format_lit.push_str(SECTION_SEPARATOR);
-    format_lit.push_str("Options:");
+    format_lit.push_str(&styled(Style::Heading, "Options:"));
+    format_lit.push_str("{options_section}");
+
     let options = fields.iter().filter(|f| f.long_name.is_some());
-    for option in options {
-        option_description(errors, &mut format_lit, option);
-    }
-    // Also include "help" and "help-json"
-    option_description_format(&mut format_lit, None, HELP_FLAG, HELP_DESCRIPTION);
-    option_description_format(&mut format_lit, None, HELP_JSON_FLAG, HELP_JSON_DESCRIPTION);
+    let (mut option_names, mut option_descriptions): (Vec<String>, Vec<String>) =
+        options.map(|option| option_help_pair(errors, option)).unzip();
+    // Also include "help", "help-json" and "help-completion"
+    option_names.push(styled_option_name(None, HELP_FLAG));
+    option_descriptions.push(HELP_DESCRIPTION.to_string());
+    option_names.push(styled_option_name(None, HELP_JSON_FLAG));
+    option_descriptions.push(HELP_JSON_DESCRIPTION.to_string());
+    option_names.push(styled_option_name(None, HELP_COMPLETION_FLAG));
+    option_descriptions.push(HELP_COMPLETION_DESCRIPTION.to_string());
+    let options_section_calculation = quote! {
+        let options_section =
+            argh_shared::wrap_section(&[#( (#option_names, #option_descriptions) ),*]);
+    };
+    let options_section_format_arg = quote! { , options_section = options_section };
 
     let subcommand_calculation;
     let subcommand_format_arg;
     if let Some(subcommand) = subcommand {
         format_lit.push_str(SECTION_SEPARATOR);
-        format_lit.push_str("Commands:{subcommands}");
+        format_lit.push_str(&styled(Style::Heading, "Commands:"));
+        format_lit.push_str("{subcommands}");
         let subcommand_ty = subcommand.ty_without_wrapper;
-        subcommand_format_arg = quote! { subcommands = subcommands };
+        subcommand_format_arg = quote! { , subcommands = subcommands };
         subcommand_calculation = quote! {
             let subcommands = argh::print_subcommands(
                 <#subcommand_ty as argh::SubCommands>::COMMANDS
@@ -84,7 +142,7 @@ pub(crate) fn help(
 
     if !ty_attrs.error_codes.is_empty() {
         format_lit.push_str(SECTION_SEPARATOR);
-        format_lit.push_str("Error codes:");
+        format_lit.push_str(&styled(Style::Heading, "Error codes:"));
         for (code, text) in &ty_attrs.error_codes {
             format_lit.push('\n');
             format_lit.push_str(INDENT);
@@ -95,8 +153,17 @@ pub(crate) fn help(
     format_lit.push('\n');
 
     quote! { {
+        #positional_section_calculation
+        #options_section_calculation
         #subcommand_calculation
-        format!(#format_lit, command_name = #cmd_name_str_array_ident.join(" "), #subcommand_format_arg)
+        let __argh_help_text = format!(
+            #format_lit,
+            command_name = #cmd_name_str_array_ident.join(" ")
+            #positional_section_format_arg
+            #options_section_format_arg
+            #subcommand_format_arg
+        );
+        argh_shared::colorize(&__argh_help_text, argh_shared::color_choice_from_env())
     } }
 }
 
@@ -104,6 +171,7 @@ struct OptionHelp {
     short: String,
     long: String,
     description: String,
+    hint: &'static str,
 }
 
 struct PositionalHelp {
@@ -128,10 +196,11 @@ impl HelpJSON {
                 retval.push_str(",\n    ");
             }
             retval.push_str(&format!(
-                "{{\"short\": \"{}\", \"long\": \"{}\", \"description\": \"{}\"}}",
+                "{{\"short\": \"{}\", \"long\": \"{}\", \"description\": \"{}\", \"hint\": \"{}\"}}",
                 opt.short,
                 opt.long,
-                escape_json(&opt.description)
+                escape_json(&opt.description),
+                opt.hint,
             ));
         }
         retval
@@ -201,18 +270,27 @@ pub(crate) fn help_json(
             short,
             long: long_with_leading_dashes.to_owned(),
             description,
+            hint: value_hint_json_tag(option.attrs.value_hint),
         });
     }
-    // Also include "help" and "help-json"
+    // Also include "help", "help-json" and "help-completion"
     help_obj.options.push(OptionHelp {
         short: String::from(""),
         long: String::from(HELP_FLAG),
         description: String::from(HELP_DESCRIPTION),
+        hint: value_hint_json_tag(ValueHint::Unknown),
     });
     help_obj.options.push(OptionHelp {
         short: String::from(""),
         long: String::from(HELP_JSON_FLAG),
         description: String::from(HELP_JSON_DESCRIPTION),
+        hint: value_hint_json_tag(ValueHint::Unknown),
+    });
+    help_obj.options.push(OptionHelp {
+        short: String::from(""),
+        long: String::from(HELP_COMPLETION_FLAG),
+        description: String::from(HELP_COMPLETION_DESCRIPTION),
+        hint: value_hint_json_tag(ValueHint::Unknown),
     });
 
     let subcommand_calculation;
@@ -265,30 +343,28 @@ pub(crate) fn help_json(
     let help_error_codes_json = HelpJSON::help_elements_json(&help_obj.error_codes);
 
     let help_description = escape_json(&help_obj.description);
-    let help_examples: TokenStream;
-    let help_notes: TokenStream;
 
     let notes_pattern = escape_json(&help_obj.notes);
     // check if we need to interpolate the string.
-    if notes_pattern.contains("{command_name}") {
-        help_notes = quote! {
+    let help_notes: TokenStream = if notes_pattern.contains("{command_name}") {
+        quote! {
             json_help_string.push_str(&format!(#notes_pattern,command_name = #cmd_name_str_array_ident.join(" ")));
-        };
+        }
     } else {
-        help_notes = quote! {
+        quote! {
             json_help_string.push_str(#notes_pattern);
-        };
-    }
+        }
+    };
     let examples_pattern = escape_json(&help_obj.examples);
-    if examples_pattern.contains("{command_name}") {
-        help_examples = quote! {
+    let help_examples: TokenStream = if examples_pattern.contains("{command_name}") {
+        quote! {
             json_help_string.push_str(&format!(#examples_pattern,command_name = #cmd_name_str_array_ident.join(" ")));
-        };
+        }
     } else {
-        help_examples = quote! {
+        quote! {
             json_help_string.push_str(#examples_pattern);
-        };
-    }
+        }
+    };
 
     quote! {{
         #subcommand_calculation
@@ -323,7 +399,7 @@ fn escape_json(value: &str) -> String {
 fn lits_section(out: &mut String, heading: &str, lits: &[syn::LitStr]) {
     if !lits.is_empty() {
         out.push_str(SECTION_SEPARATOR);
-        out.push_str(heading);
+        out.push_str(&styled(Style::Heading, heading));
         for lit in lits {
             let value = lit.value();
             for line in value.split('\n') {
@@ -340,18 +416,47 @@ fn positional_usage(out: &mut String, field: &StructField<'_>) {
     if !field.optionality.is_required() {
         out.push('[');
     }
-    out.push('<');
-    let name = field.arg_name();
-    out.push_str(&name);
+    let mut placeholder = "<".to_string();
+    placeholder.push_str(&field.arg_name());
     if field.optionality == Optionality::Repeating {
-        out.push_str("...");
+        placeholder.push_str("...");
     }
-    out.push('>');
+    placeholder.push('>');
+    out.push_str(&styled(Style::Placeholder, &placeholder));
     if !field.optionality.is_required() {
         out.push(']');
     }
 }
 
+/// The placeholder name implied by a `#[argh(value_hint = "...")]` hint,
+/// used in place of the long-name stem when no explicit `arg_name` is set.
+/// Returns `None` for `ValueHint::Unknown`, preserving current behavior.
+fn value_hint_placeholder(hint: ValueHint) -> Option<&'static str> {
+    match hint {
+        ValueHint::Unknown => None,
+        ValueHint::FilePath => Some("file"),
+        ValueHint::DirPath => Some("dir"),
+        ValueHint::ExecutablePath => Some("exe"),
+        ValueHint::Hostname => Some("host"),
+        ValueHint::Url => Some("url"),
+        ValueHint::Username => Some("user"),
+    }
+}
+
+/// The JSON tag for a `ValueHint`, included in `help_json` output so GUIs
+/// built on the JSON help can render an appropriate picker per option.
+fn value_hint_json_tag(hint: ValueHint) -> &'static str {
+    match hint {
+        ValueHint::Unknown => "unknown",
+        ValueHint::FilePath => "file_path",
+        ValueHint::DirPath => "dir_path",
+        ValueHint::ExecutablePath => "executable_path",
+        ValueHint::Hostname => "hostname",
+        ValueHint::Url => "url",
+        ValueHint::Username => "username",
+    }
+}
+
 /// Add options like `[-f <foo>]` to a help format string.
 /// This function must only be called on options (things with `long_name.is_some()`)
 fn option_usage(out: &mut String, field: &StructField<'_>) {
@@ -361,27 +466,31 @@ fn option_usage(out: &mut String, field: &StructField<'_>) {
     }
 
     let long_name = field.long_name.as_ref().expect("missing long name for option");
-    if let Some(short) = field.attrs.short.as_ref() {
-        out.push('-');
-        out.push(short.value());
+    let flag_name = if let Some(short) = field.attrs.short.as_ref() {
+        format!("-{}", short.value())
     } else {
-        out.push_str(long_name);
-    }
+        long_name.to_owned()
+    };
+    out.push_str(&styled(Style::FlagName, &flag_name));
 
     match field.kind {
         FieldKind::SubCommand | FieldKind::Positional => unreachable!(), // don't have long_name
         FieldKind::Switch => {}
         FieldKind::Option => {
-            out.push_str(" <");
+            out.push(' ');
+            let mut placeholder = "<".to_string();
             if let Some(arg_name) = &field.attrs.arg_name {
-                out.push_str(&arg_name.value());
+                placeholder.push_str(&arg_name.value());
+            } else if let Some(hint_name) = value_hint_placeholder(field.attrs.value_hint) {
+                placeholder.push_str(hint_name);
             } else {
-                out.push_str(long_name.trim_start_matches("--"));
+                placeholder.push_str(long_name.trim_start_matches("--"));
             }
             if field.optionality == Optionality::Repeating {
-                out.push_str("...");
+                placeholder.push_str("...");
             }
-            out.push('>');
+            placeholder.push('>');
+            out.push_str(&styled(Style::Placeholder, &placeholder));
         }
     }
 
@@ -411,41 +520,32 @@ Add a doc comment or an `#[argh(description = \"...\")]` attribute.",
     })
 }
 
-/// Describes a positional argument like this:
+/// Returns the `(name, description)` pair for a positional argument, like
 ///  hello       positional argument description
-fn positional_description(out: &mut String, field: &StructField<'_>) {
-    let field_name = field.arg_name();
-
+fn positional_help_pair(field: &StructField<'_>) -> (String, String) {
+    let name = field.arg_name();
     let mut description = String::from("");
     if let Some(desc) = &field.attrs.description {
         description = desc.content.value().trim().to_owned();
     }
-    positional_description_format(out, &field_name, &description)
+    (name, description)
 }
 
-fn positional_description_format(out: &mut String, name: &str, description: &str) {
-    let info = argh_shared::CommandInfo { name: &*name, description };
-    argh_shared::write_description(out, &info);
-}
-
-/// Describes an option like this:
+/// Returns the `(name, description)` pair for an option, like
 ///  -f, --force       force, ignore minor errors. This description
 ///                    is so long that it wraps to the next line.
-fn option_description(errors: &Errors, out: &mut String, field: &StructField<'_>) {
+/// `name` already carries its `Style::FlagName` markers.
+fn option_help_pair(errors: &Errors, field: &StructField<'_>) -> (String, String) {
     let short = field.attrs.short.as_ref().map(|s| s.value());
     let long_with_leading_dashes = field.long_name.as_ref().expect("missing long name for option");
     let description =
         require_description(errors, field.name.span(), &field.attrs.description, "field");
-
-    option_description_format(out, short, long_with_leading_dashes, &description)
+    (styled_option_name(short, long_with_leading_dashes), description)
 }
 
-fn option_description_format(
-    out: &mut String,
-    short: Option<char>,
-    long_with_leading_dashes: &str,
-    description: &str,
-) {
+/// Renders an option's `-f, --force`-style name, wrapped in its
+/// `Style::FlagName` markers.
+fn styled_option_name(short: Option<char>, long_with_leading_dashes: &str) -> String {
     let mut name = String::new();
     if let Some(short) = short {
         name.push('-');
@@ -453,9 +553,7 @@ fn option_description_format(
         name.push_str(", ");
     }
     name.push_str(long_with_leading_dashes);
-
-    let info = argh_shared::CommandInfo { name: &*name, description };
-    argh_shared::write_description(out, &info);
+    styled(Style::FlagName, &name)
 }
 
 /// Builds the usage description command line and appends it to "out".
@@ -481,10 +579,79 @@ pub(crate) fn build_usage_command_line(
         if !subcommand.optionality.is_required() {
             out.push('[');
         }
-        out.push_str("<command>");
+        out.push_str(&styled(Style::Placeholder, "<command>"));
         if !subcommand.optionality.is_required() {
             out.push(']');
         }
-        out.push_str(" [<args>]");
+        out.push_str(" [");
+        out.push_str(&styled(Style::Placeholder, "<args>"));
+        out.push(']');
+    }
+}
+
+/// Returns a `TokenStream` for a const `argh_shared::CompletionInfo` struct
+/// literal describing this type's own flags/positionals, for
+/// `argh::Completion::COMPLETION_INFO`.
+///
+/// Walks the same `fields`/`subcommand` structure as [`help`], so completion
+/// always matches what `--help` documents; each option's `value_hint` tags
+/// along so e.g. a `FilePath`-hinted option completes with files instead of
+/// a bare flag. When `subcommand` is present, `subcommands` is borrowed
+/// straight from the subcommand enum's own `COMPLETION_INFO`, so a
+/// completion script can recurse into a subcommand's own option set the same
+/// way `--help` recurses into its own `FromArgs::from_args`.
+///
+/// Note: `fields` entries with `is_subcommand.is_some()` will be ignored
+/// in favor of the `subcommand` argument.
+pub(crate) fn completion_info_tokens(
+    fields: &[StructField<'_>],
+    subcommand: Option<&StructField<'_>>,
+) -> TokenStream {
+    let mut long_flags: Vec<String> = Vec::new();
+    let mut short_flags: Vec<String> = Vec::new();
+    let mut option_hint_flags: Vec<String> = Vec::new();
+    let mut option_hint_descriptions: Vec<String> = Vec::new();
+    let mut option_hint_value_hints: Vec<&'static str> = Vec::new();
+
+    for field in fields.iter().filter(|f| f.long_name.is_some()) {
+        let long_name = field.long_name.as_ref().expect("missing long name for option").to_owned();
+        if let Some(short) = field.attrs.short.as_ref() {
+            short_flags.push(format!("-{}", short.value()));
+        }
+        let description = field
+            .attrs
+            .description
+            .as_ref()
+            .map(|d| d.content.value().trim().to_owned())
+            .unwrap_or_default();
+        option_hint_flags.push(long_name.clone());
+        option_hint_descriptions.push(description);
+        option_hint_value_hints.push(value_hint_json_tag(field.attrs.value_hint));
+        long_flags.push(long_name);
+    }
+    long_flags.push(HELP_FLAG.to_string());
+    long_flags.push(HELP_JSON_FLAG.to_string());
+    long_flags.push(HELP_COMPLETION_FLAG.to_string());
+
+    let positional_names: Vec<String> =
+        fields.iter().filter(|f| f.kind == FieldKind::Positional).map(|f| f.arg_name()).collect();
+
+    let subcommands_tokens = if let Some(subcommand) = subcommand {
+        let subcommand_ty = subcommand.ty_without_wrapper;
+        quote! { <#subcommand_ty as argh::Completion>::COMPLETION_INFO.subcommands }
+    } else {
+        quote! { &[] }
+    };
+
+    quote! {
+        argh_shared::CompletionInfo {
+            long_flags: &[#(#long_flags),*],
+            short_flags: &[#(#short_flags),*],
+            positional_names: &[#(#positional_names),*],
+            option_hints: &[
+                #( (#option_hint_flags, #option_hint_descriptions, #option_hint_value_hints) ),*
+            ],
+            subcommands: #subcommands_tokens,
+        }
     }
 }
@@ -0,0 +1,504 @@
+// Copyright (c) 2020 Google LLC All rights reserved.
+// Use of this source code is governed by a BSD-style
+// license that can be found in the LICENSE file.
+
+//! The derive macro for `argh`'s `FromArgs` trait. See the `argh` crate's
+//! documentation for usage.
+
+mod errors;
+mod help;
+mod parse_attrs;
+
+use {
+    crate::{
+        errors::Errors,
+        parse_attrs::{FieldAttrs, FieldKind, TypeAttrs},
+    },
+    proc_macro2::TokenStream,
+    quote::{format_ident, quote},
+    syn::{spanned::Spanned, Data, DeriveInput, Fields},
+};
+
+/// Whether a field's value is required, optional, or may repeat.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Optionality {
+    Required,
+    Optional,
+    Repeating,
+}
+
+impl Optionality {
+    pub(crate) fn is_required(&self) -> bool {
+        matches!(self, Optionality::Required)
+    }
+}
+
+/// One field of a `#[derive(FromArgs)]` struct, with its attributes parsed
+/// and its `Option`/`Vec` wrapper (if any) stripped off.
+pub(crate) struct StructField<'a> {
+    pub kind: FieldKind,
+    pub long_name: Option<String>,
+    pub name: &'a syn::Ident,
+    pub attrs: &'a FieldAttrs,
+    pub ty_without_wrapper: &'a syn::Type,
+    pub optionality: Optionality,
+}
+
+impl StructField<'_> {
+    /// The name used for this field in `--help` and shell completion: an
+    /// explicit `#[argh(arg_name = "...")]`, else the long flag name with
+    /// its leading dashes stripped, else the field's own name with
+    /// underscores turned into dashes.
+    pub(crate) fn arg_name(&self) -> String {
+        if let Some(arg_name) = &self.attrs.arg_name {
+            return arg_name.value();
+        }
+        if let Some(long_name) = &self.long_name {
+            return long_name.trim_start_matches('-').to_owned();
+        }
+        self.name.to_string().replace('_', "-")
+    }
+}
+
+fn to_kebab_case(name: &str) -> String {
+    name.replace('_', "-")
+}
+
+/// Strips a field's `Option<T>`/`Vec<T>` wrapper, returning the inner type
+/// and the resulting [`Optionality`].
+fn strip_wrapper(ty: &syn::Type) -> (&syn::Type, Optionality) {
+    if let Some(inner) = unwrap_generic(ty, "Option") {
+        return (inner, Optionality::Optional);
+    }
+    if let Some(inner) = unwrap_generic(ty, "Vec") {
+        return (inner, Optionality::Repeating);
+    }
+    (ty, Optionality::Required)
+}
+
+fn unwrap_generic<'a>(ty: &'a syn::Type, wrapper: &str) -> Option<&'a syn::Type> {
+    let syn::Type::Path(path) = ty else { return None };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else { return None };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+#[proc_macro_derive(FromArgs, attributes(argh))]
+pub fn from_args_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = match syn::parse(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let errors = Errors::default();
+    let tokens = match &input.data {
+        Data::Struct(data) => impl_from_args_struct(&errors, &input, data),
+        Data::Enum(data) => impl_from_args_enum(&errors, &input, data),
+        Data::Union(_) => {
+            errors.err_span(input.span(), "#[derive(FromArgs)] cannot be applied to unions");
+            TokenStream::new()
+        }
+    };
+    quote! {
+        #tokens
+        #errors
+    }
+    .into()
+}
+
+fn field_attrs_and_kind(
+    errors: &Errors,
+    field: &syn::Field,
+) -> (FieldAttrs, FieldKind) {
+    let attrs = FieldAttrs::parse(errors, field);
+    let kind = attrs.kind.unwrap_or_else(|| {
+        errors.err_span(
+            field.span(),
+            "fields must specify `#[argh(switch)]`, `#[argh(option)]`, \
+             `#[argh(positional)]`, or `#[argh(subcommand)]`",
+        );
+        FieldKind::Option
+    });
+    (attrs, kind)
+}
+
+struct ParsedField<'a> {
+    field: &'a syn::Field,
+    name: &'a syn::Ident,
+    attrs: FieldAttrs,
+    kind: FieldKind,
+    ty_without_wrapper: syn::Type,
+    optionality: Optionality,
+    long_name: Option<String>,
+}
+
+fn parse_fields<'a>(errors: &Errors, fields: &'a Fields) -> Vec<ParsedField<'a>> {
+    let Fields::Named(named) = fields else {
+        errors.err_span(fields.span(), "#[derive(FromArgs)] requires named fields");
+        return Vec::new();
+    };
+    named
+        .named
+        .iter()
+        .map(|field| {
+            let name = field.ident.as_ref().expect("named field without an ident");
+            let (attrs, kind) = field_attrs_and_kind(errors, field);
+            let (ty_without_wrapper, optionality) = strip_wrapper(&field.ty);
+            let long_name = match kind {
+                FieldKind::Switch | FieldKind::Option => Some(
+                    attrs
+                        .long
+                        .as_ref()
+                        .map(|l| l.value())
+                        .unwrap_or_else(|| format!("--{}", to_kebab_case(&name.to_string()))),
+                ),
+                FieldKind::Positional | FieldKind::SubCommand => None,
+            };
+            ParsedField {
+                field,
+                name,
+                attrs,
+                kind,
+                ty_without_wrapper: ty_without_wrapper.clone(),
+                optionality,
+                long_name,
+            }
+        })
+        .collect()
+}
+
+fn struct_fields_for_help<'a>(parsed: &'a [ParsedField<'a>]) -> Vec<StructField<'a>> {
+    parsed
+        .iter()
+        .filter(|f| f.kind != FieldKind::SubCommand)
+        .map(|f| StructField {
+            kind: f.kind,
+            long_name: f.long_name.clone(),
+            name: f.name,
+            attrs: &f.attrs,
+            ty_without_wrapper: &f.ty_without_wrapper,
+            optionality: f.optionality,
+        })
+        .collect()
+}
+
+fn subcommand_field<'a>(parsed: &'a [ParsedField<'a>]) -> Option<StructField<'a>> {
+    parsed.iter().find(|f| f.kind == FieldKind::SubCommand).map(|f| StructField {
+        kind: f.kind,
+        long_name: f.long_name.clone(),
+        name: f.name,
+        attrs: &f.attrs,
+        ty_without_wrapper: &f.ty_without_wrapper,
+        optionality: f.optionality,
+    })
+}
+
+fn impl_from_args_struct(
+    errors: &Errors,
+    input: &DeriveInput,
+    data: &syn::DataStruct,
+) -> TokenStream {
+    let name = &input.ident;
+    let ty_attrs = TypeAttrs::parse(errors, &input.attrs);
+    let parsed = parse_fields(errors, &data.fields);
+    let help_fields = struct_fields_for_help(&parsed);
+    let subcommand = subcommand_field(&parsed);
+
+    let cmd_name_ident = format_ident!("__argh_command_name");
+    let help_tokens =
+        help::help(errors, &cmd_name_ident, &ty_attrs, &help_fields, subcommand.as_ref());
+    let help_json_tokens =
+        help::help_json(errors, &cmd_name_ident, &ty_attrs, &help_fields, subcommand.as_ref());
+    let completion_info_tokens = help::completion_info_tokens(&help_fields, subcommand.as_ref());
+
+    let mut decls = TokenStream::new();
+    let mut arms = TokenStream::new();
+    let mut field_inits = TokenStream::new();
+    let mut positional_idx = 0usize;
+    let mut positional_arms = Vec::new();
+    let mut subcommand_name = None;
+
+    for field in &parsed {
+        let field_name = field.name;
+        let ty = &field.field.ty;
+        let local = format_ident!("__argh_field_{}", field_name);
+        match field.kind {
+            FieldKind::Switch => {
+                decls.extend(quote! { let mut #local: bool = false; });
+                let long = field.long_name.as_deref().unwrap();
+                let short_arm = if let Some(short) = &field.attrs.short {
+                    let short_flag = format!("-{}", short.value());
+                    quote! { #short_flag | }
+                } else {
+                    TokenStream::new()
+                };
+                arms.extend(quote! {
+                    #short_arm #long => {
+                        #local = true;
+                        __argh_idx += 1;
+                        continue;
+                    }
+                });
+                field_inits.extend(quote! { #field_name: #local, });
+            }
+            FieldKind::Option => {
+                let inner_ty = &field.ty_without_wrapper;
+                decls.extend(quote! { let mut #local: Vec<#inner_ty> = Vec::new(); });
+                let long = field.long_name.as_deref().unwrap();
+                let short_arm = if let Some(short) = &field.attrs.short {
+                    let short_flag = format!("-{}", short.value());
+                    quote! { #short_flag | }
+                } else {
+                    TokenStream::new()
+                };
+                let flag_display = long.to_string();
+                arms.extend(quote! {
+                    #short_arm #long => {
+                        __argh_idx += 1;
+                        let __argh_raw = __argh_args.get(__argh_idx).copied().ok_or_else(|| {
+                            argh::EarlyExit::from_message(format!(
+                                "missing value for option `{}`", #flag_display
+                            ))
+                        })?;
+                        let __argh_value: #inner_ty = __argh_raw.parse().map_err(|_| {
+                            argh::EarlyExit::from_message(format!(
+                                "invalid value for `{}`: {}", #flag_display, __argh_raw
+                            ))
+                        })?;
+                        #local.push(__argh_value);
+                        __argh_idx += 1;
+                        continue;
+                    }
+                });
+                match field.optionality {
+                    Optionality::Repeating => {
+                        field_inits.extend(quote! { #field_name: #local, });
+                    }
+                    Optionality::Optional => {
+                        field_inits.extend(quote! { #field_name: #local.pop(), });
+                    }
+                    Optionality::Required => {
+                        field_inits.extend(quote! {
+                            #field_name: #local.pop().ok_or_else(|| {
+                                argh::EarlyExit::from_message(format!(
+                                    "required option `{}` was not provided", #flag_display
+                                ))
+                            })?,
+                        });
+                    }
+                }
+            }
+            FieldKind::Positional => {
+                let inner_ty = &field.ty_without_wrapper;
+                decls.extend(quote! { let mut #local: Vec<#inner_ty> = Vec::new(); });
+                let arg_name = field.attrs.arg_name.as_ref().map(|l| l.value()).unwrap_or_else(
+                    || field_name.to_string().replace('_', "-"),
+                );
+                let idx = positional_idx;
+                positional_idx += 1;
+                let repeating = field.optionality == Optionality::Repeating;
+                positional_arms.push(quote! {
+                    if __argh_positional_idx == #idx {
+                        let __argh_value: #inner_ty = __argh_arg.parse().map_err(|_| {
+                            argh::EarlyExit::from_message(format!(
+                                "invalid value for `{}`: {}", #arg_name, __argh_arg
+                            ))
+                        })?;
+                        #local.push(__argh_value);
+                        if !#repeating {
+                            __argh_positional_idx += 1;
+                        }
+                        __argh_idx += 1;
+                        continue;
+                    }
+                });
+                match field.optionality {
+                    Optionality::Repeating => {
+                        field_inits.extend(quote! { #field_name: #local, });
+                    }
+                    Optionality::Optional => {
+                        field_inits.extend(quote! { #field_name: #local.pop(), });
+                    }
+                    Optionality::Required => {
+                        field_inits.extend(quote! {
+                            #field_name: #local.pop().ok_or_else(|| {
+                                argh::EarlyExit::from_message(format!(
+                                    "required positional argument `{}` was not provided", #arg_name
+                                ))
+                            })?,
+                        });
+                    }
+                }
+            }
+            FieldKind::SubCommand => {
+                subcommand_name = Some((field_name, ty.clone()));
+            }
+        }
+    }
+
+    let subcommand_dispatch = if let Some((field_name, ty)) = &subcommand_name {
+        quote! {
+            if __argh_idx < __argh_args.len() && !__argh_args[__argh_idx].starts_with('-') {
+                let __argh_sub_name = __argh_command_name.to_vec();
+                let __argh_sub_args = &__argh_args[__argh_idx..];
+                let __argh_sub = <#ty as argh::FromArgs>::from_args(&__argh_sub_name, __argh_sub_args)?;
+                return Ok(Self { #field_name: __argh_sub, #field_inits });
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    let positional_arms = positional_arms.into_iter().collect::<TokenStream>();
+
+    let final_result = if subcommand_name.is_some() {
+        quote! {
+            Err(argh::EarlyExit::from_message("a subcommand is required".to_owned()))
+        }
+    } else {
+        quote! {
+            Ok(Self { #field_inits })
+        }
+    };
+
+    quote! {
+        impl argh::FromArgs for #name {
+            fn from_args(
+                __argh_command_name: &[&str],
+                __argh_args: &[&str],
+            ) -> std::result::Result<Self, argh::EarlyExit> {
+                #decls
+                let mut __argh_idx = 0usize;
+                let mut __argh_positional_idx = 0usize;
+                while __argh_idx < __argh_args.len() {
+                    let __argh_arg = __argh_args[__argh_idx];
+                    match __argh_arg {
+                        "--help" | "-h" => {
+                            return Err(argh::EarlyExit::from_output(#help_tokens));
+                        }
+                        "--help-json" => {
+                            return Err(argh::EarlyExit::from_output(#help_json_tokens));
+                        }
+                        "--help-completion" => {
+                            __argh_idx += 1;
+                            let shell = __argh_args.get(__argh_idx).copied().unwrap_or("");
+                            return Err(argh::EarlyExit::from_output(
+                                argh_shared::generate_completion(
+                                    shell,
+                                    &__argh_command_name.join(" "),
+                                    &<Self as argh::Completion>::COMPLETION_INFO,
+                                )
+                            ));
+                        }
+                        #arms
+                        _ => {
+                            #subcommand_dispatch
+                            #positional_arms
+                            return Err(argh::EarlyExit::from_message(format!(
+                                "unrecognized argument: {}", __argh_arg
+                            )));
+                        }
+                    }
+                }
+                #final_result
+            }
+        }
+
+        impl argh::Completion for #name {
+            const COMPLETION_INFO: argh_shared::CompletionInfo = #completion_info_tokens;
+        }
+    }
+}
+
+fn impl_from_args_enum(errors: &Errors, input: &DeriveInput, data: &syn::DataEnum) -> TokenStream {
+    let name = &input.ident;
+    let mut dispatch_arms = TokenStream::new();
+    let mut command_infos = Vec::new();
+    let mut completion_subcommands = Vec::new();
+
+    for variant in &data.variants {
+        let variant_name = &variant.ident;
+        let Fields::Unnamed(unnamed) = &variant.fields else {
+            errors.err_span(
+                variant.span(),
+                "#[derive(FromArgs)] subcommand enum variants must wrap a single type",
+            );
+            continue;
+        };
+        let Some(field) = unnamed.unnamed.first() else {
+            errors.err_span(variant.span(), "subcommand variant must wrap exactly one type");
+            continue;
+        };
+        let inner_ty = &field.ty;
+        let attrs = FieldAttrs::parse(errors, &syn::Field {
+            attrs: variant.attrs.clone(),
+            vis: syn::Visibility::Inherited,
+            mutability: syn::FieldMutability::None,
+            ident: None,
+            colon_token: None,
+            ty: field.ty.clone(),
+        });
+        let command_name = attrs
+            .long
+            .as_ref()
+            .map(|l| l.value())
+            .unwrap_or_else(|| to_kebab_case(&variant_name.to_string().to_lowercase()));
+        let description = attrs
+            .description
+            .as_ref()
+            .map(|d| d.content.value().trim().to_owned())
+            .unwrap_or_default();
+        command_infos.push(quote! {
+            &argh::CommandInfo { name: #command_name, description: #description }
+        });
+        completion_subcommands.push(quote! {
+            (#command_name, &<#inner_ty as argh::Completion>::COMPLETION_INFO)
+        });
+        dispatch_arms.extend(quote! {
+            #command_name => {
+                let mut __argh_sub_name = command_name.to_vec();
+                __argh_sub_name.push(#command_name);
+                let __argh_sub = <#inner_ty as argh::FromArgs>::from_args(&__argh_sub_name, &args[1..])?;
+                return Ok(#name::#variant_name(__argh_sub));
+            }
+        });
+    }
+
+    quote! {
+        impl argh::FromArgs for #name {
+            fn from_args(
+                command_name: &[&str],
+                args: &[&str],
+            ) -> std::result::Result<Self, argh::EarlyExit> {
+                let Some(&__argh_sub_command) = args.first() else {
+                    return Err(argh::EarlyExit::from_message("a subcommand is required".to_owned()));
+                };
+                match __argh_sub_command {
+                    #dispatch_arms
+                    other => Err(argh::EarlyExit::from_message(format!(
+                        "unrecognized command: {}", other
+                    ))),
+                }
+            }
+        }
+
+        impl argh::SubCommands for #name {
+            const COMMANDS: &'static [&'static argh::CommandInfo] = &[#(#command_infos),*];
+        }
+
+        impl argh::Completion for #name {
+            const COMPLETION_INFO: argh_shared::CompletionInfo = argh_shared::CompletionInfo {
+                long_flags: &[],
+                short_flags: &[],
+                positional_names: &[],
+                option_hints: &[],
+                subcommands: &[#(#completion_subcommands),*],
+            };
+        }
+    }
+}
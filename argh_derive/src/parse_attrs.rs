@@ -0,0 +1,265 @@
+// Copyright (c) 2020 Google LLC All rights reserved.
+// Use of this source code is governed by a BSD-style
+// license that can be found in the LICENSE file.
+
+use {
+    crate::errors::Errors,
+    syn::{punctuated::Punctuated, spanned::Spanned, Meta, Token},
+};
+
+/// The four kinds of fields a `#[derive(FromArgs)]` struct can have,
+/// selected by the `#[argh(switch | option | positional | subcommand)]`
+/// attribute on the field.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FieldKind {
+    /// `#[argh(switch)]`: a boolean flag with no value, e.g. `--verbose`.
+    Switch,
+    /// `#[argh(option)]`: a flag taking a value, e.g. `--name <name>`.
+    Option,
+    /// `#[argh(subcommand)]`: an enum of further `#[derive(FromArgs)]` types.
+    SubCommand,
+    /// `#[argh(positional)]`: a bare positional argument.
+    Positional,
+}
+
+/// A placeholder hint for an option's value, used to pick a more specific
+/// `<placeholder>` in `--help` and a more useful shell-completion widget
+/// than a bare flag name. Set with `#[argh(value_hint = "...")]`; the
+/// accepted strings are this enum's variants other than `Unknown`, in
+/// `snake_case`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ValueHint {
+    /// No particular hint (the default).
+    #[default]
+    Unknown,
+    /// The value names a file.
+    FilePath,
+    /// The value names a directory.
+    DirPath,
+    /// The value names an executable on `PATH`.
+    ExecutablePath,
+    /// The value is a hostname.
+    Hostname,
+    /// The value is a URL.
+    Url,
+    /// The value is a username.
+    Username,
+}
+
+impl ValueHint {
+    fn parse(errors: &Errors, lit: &syn::LitStr) -> Self {
+        match lit.value().as_str() {
+            "file_path" => ValueHint::FilePath,
+            "dir_path" => ValueHint::DirPath,
+            "executable_path" => ValueHint::ExecutablePath,
+            "hostname" => ValueHint::Hostname,
+            "url" => ValueHint::Url,
+            "username" => ValueHint::Username,
+            other => {
+                errors.err_span(
+                    lit.span(),
+                    &format!(
+                        "unrecognized `value_hint`: \"{other}\" (expected one of \"file_path\", \
+                         \"dir_path\", \"executable_path\", \"hostname\", \"url\", \"username\")"
+                    ),
+                );
+                ValueHint::Unknown
+            }
+        }
+    }
+}
+
+/// A description string, either from an explicit `#[argh(description = "...")]`
+/// or from the item's doc comment.
+pub struct Description {
+    pub content: syn::LitStr,
+}
+
+/// The `#[argh(...)]` attributes recognized on a struct field.
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub kind: Option<FieldKind>,
+    pub description: Option<Description>,
+    pub short: Option<syn::LitChar>,
+    pub long: Option<syn::LitStr>,
+    pub arg_name: Option<syn::LitStr>,
+    pub value_hint: ValueHint,
+}
+
+impl FieldAttrs {
+    pub fn parse(errors: &Errors, field: &syn::Field) -> Self {
+        let mut attrs =
+            FieldAttrs { description: doc_comment_description(field.attrs.iter()), ..Default::default() };
+
+        for argh_attr in field.attrs.iter().filter(|a| a.path().is_ident("argh")) {
+            let metas = match argh_attr
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            {
+                Ok(metas) => metas,
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                }
+            };
+            for meta in metas {
+                parse_field_meta(errors, &mut attrs, &meta);
+            }
+        }
+
+        attrs
+    }
+}
+
+fn parse_field_meta(errors: &Errors, attrs: &mut FieldAttrs, meta: &Meta) {
+    let path = meta.path();
+    if path.is_ident("switch") {
+        attrs.kind = Some(FieldKind::Switch);
+    } else if path.is_ident("option") {
+        attrs.kind = Some(FieldKind::Option);
+    } else if path.is_ident("positional") {
+        attrs.kind = Some(FieldKind::Positional);
+    } else if path.is_ident("subcommand") {
+        attrs.kind = Some(FieldKind::SubCommand);
+    } else if path.is_ident("description") {
+        if let Some(lit) = expect_lit_str(errors, meta) {
+            attrs.description = Some(Description { content: lit });
+        }
+    } else if path.is_ident("short") {
+        if let Some(lit) = expect_lit_char(errors, meta) {
+            attrs.short = Some(lit);
+        }
+    } else if path.is_ident("long") {
+        if let Some(lit) = expect_lit_str(errors, meta) {
+            attrs.long = Some(lit);
+        }
+    } else if path.is_ident("arg_name") {
+        if let Some(lit) = expect_lit_str(errors, meta) {
+            attrs.arg_name = Some(lit);
+        }
+    } else if path.is_ident("value_hint") {
+        if let Some(lit) = expect_lit_str(errors, meta) {
+            attrs.value_hint = ValueHint::parse(errors, &lit);
+        }
+    } else {
+        errors.err_span(path.span(), "unrecognized `#[argh(...)]` attribute");
+    }
+}
+
+/// The `#[argh(...)]` attributes recognized on the derived struct/enum itself.
+#[derive(Default)]
+pub struct TypeAttrs {
+    pub description: Option<Description>,
+    pub examples: Vec<syn::LitStr>,
+    pub notes: Vec<syn::LitStr>,
+    pub error_codes: Vec<(syn::LitInt, syn::LitStr)>,
+}
+
+impl TypeAttrs {
+    pub fn parse(errors: &Errors, attrs: &[syn::Attribute]) -> Self {
+        let mut type_attrs =
+            TypeAttrs { description: doc_comment_description(attrs.iter()), ..Default::default() };
+
+        for argh_attr in attrs.iter().filter(|a| a.path().is_ident("argh")) {
+            let metas = match argh_attr
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            {
+                Ok(metas) => metas,
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                }
+            };
+            for meta in metas {
+                let path = meta.path();
+                if path.is_ident("description") {
+                    if let Some(lit) = expect_lit_str(errors, &meta) {
+                        type_attrs.description = Some(Description { content: lit });
+                    }
+                } else if path.is_ident("example") {
+                    if let Some(lit) = expect_lit_str(errors, &meta) {
+                        type_attrs.examples.push(lit);
+                    }
+                } else if path.is_ident("note") {
+                    if let Some(lit) = expect_lit_str(errors, &meta) {
+                        type_attrs.notes.push(lit);
+                    }
+                } else if path.is_ident("error_code") {
+                    match meta.require_list().and_then(|list| {
+                        list.parse_args_with(Punctuated::<syn::Lit, Token![,]>::parse_terminated)
+                    }) {
+                        Ok(args) => {
+                            let mut iter = args.into_iter();
+                            match (iter.next(), iter.next()) {
+                                (
+                                    Some(syn::Lit::Int(code)),
+                                    Some(syn::Lit::Str(description)),
+                                ) => {
+                                    type_attrs.error_codes.push((code, description));
+                                }
+                                _ => errors.err_span(
+                                    path.span(),
+                                    "expected `error_code(<integer>, \"<description>\")`",
+                                ),
+                            }
+                        }
+                        Err(err) => errors.push(err),
+                    }
+                } else {
+                    errors.err_span(path.span(), "unrecognized `#[argh(...)]` attribute");
+                }
+            }
+        }
+
+        type_attrs
+    }
+}
+
+fn expect_lit_str(errors: &Errors, meta: &Meta) -> Option<syn::LitStr> {
+    match meta.require_name_value().map(|nv| &nv.value) {
+        Ok(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. })) => Some(lit.clone()),
+        Ok(other) => {
+            errors.err_span(syn::spanned::Spanned::span(other), "expected a string literal");
+            None
+        }
+        Err(err) => {
+            errors.push(err);
+            None
+        }
+    }
+}
+
+fn expect_lit_char(errors: &Errors, meta: &Meta) -> Option<syn::LitChar> {
+    match meta.require_name_value().map(|nv| &nv.value) {
+        Ok(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Char(lit), .. })) => Some(lit.clone()),
+        Ok(other) => {
+            errors.err_span(syn::spanned::Spanned::span(other), "expected a character literal");
+            None
+        }
+        Err(err) => {
+            errors.push(err);
+            None
+        }
+    }
+}
+
+/// Builds a `Description` from a `///` doc comment, joining multiple lines
+/// with spaces after trimming each line's leading and trailing whitespace.
+fn doc_comment_description<'a>(
+    attrs: impl Iterator<Item = &'a syn::Attribute>,
+) -> Option<Description> {
+    let mut doc = String::new();
+    for attr in attrs.filter(|a| a.path().is_ident("doc")) {
+        let Meta::NameValue(nv) = &attr.meta else { continue };
+        if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = &nv.value {
+            if !doc.is_empty() {
+                doc.push(' ');
+            }
+            doc.push_str(lit.value().trim());
+        }
+    }
+    if doc.is_empty() {
+        None
+    } else {
+        Some(Description { content: syn::LitStr::new(&doc, proc_macro2::Span::call_site()) })
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2020 Google LLC All rights reserved.
+// Use of this source code is governed by a BSD-style
+// license that can be found in the LICENSE file.
+
+use argh::FromArgs;
+
+/// A tool that does a thing.
+#[derive(FromArgs, Debug)]
+struct TopLevel {
+    /// be verbose
+    #[argh(switch, short = 'v')]
+    verbose: bool,
+
+    /// the thing's name
+    #[argh(option)]
+    name: Option<String>,
+
+    /// a path to read from
+    #[argh(option, value_hint = "file_path")]
+    input: Option<String>,
+
+    /// an output path
+    #[argh(positional)]
+    output: String,
+}
+
+fn parse(args: &[&str]) -> Result<TopLevel, argh::EarlyExit> {
+    TopLevel::from_args(&["cmd"], args)
+}
+
+#[test]
+fn parses_switches_options_and_positionals() {
+    parse(&["-v", "--name", "foo", "--", "out.txt"])
+        .expect_err("`--` fills the positional, leaving `out.txt` unrecognized")
+        .status
+        .expect_err("should be a parse error");
+
+    let parsed =
+        parse(&["-v", "--name", "foo", "--input", "in.txt", "out.txt"]).expect("should parse");
+    assert!(parsed.verbose);
+    assert_eq!(parsed.name.as_deref(), Some("foo"));
+    assert_eq!(parsed.input.as_deref(), Some("in.txt"));
+    assert_eq!(parsed.output, "out.txt");
+}
+
+#[test]
+fn missing_required_positional_is_an_error() {
+    let err = parse(&["-v"]).expect_err("output is required");
+    assert!(err.status.is_err());
+    assert!(err.output.contains("output"));
+}
+
+#[test]
+fn help_includes_flags_and_value_hint_placeholder() {
+    let err = parse(&["--help"]).expect_err("--help always exits early");
+    assert!(err.status.is_ok());
+    assert!(err.output.contains("--name"));
+    assert!(err.output.contains("<file>"));
+}
+
+#[test]
+fn help_json_includes_value_hint_tag() {
+    let err = parse(&["--help-json"]).expect_err("--help-json always exits early");
+    assert!(err.output.contains("\"hint\": \"file_path\""));
+}
+
+#[test]
+fn help_completion_generates_a_bash_script() {
+    let err = parse(&["--help-completion", "bash"]).expect_err("always exits early");
+    assert!(err.output.contains("complete -F"));
+    assert!(err.output.contains("--name"));
+}
+
+#[test]
+fn help_completion_completes_positional_arguments() {
+    let err = parse(&["--help-completion", "bash"]).expect_err("always exits early");
+    assert!(err.output.contains("compgen -f"));
+}
+
+/// A tool with subcommands.
+#[derive(FromArgs, Debug)]
+struct WithSubcommand {
+    #[argh(subcommand)]
+    command: Command,
+}
+
+#[derive(FromArgs, Debug)]
+enum Command {
+    /// build the thing
+    #[argh(subcommand, long = "build")]
+    Build(BuildCommand),
+}
+
+/// build the thing
+#[derive(FromArgs, Debug)]
+struct BuildCommand {
+    /// build in release mode
+    #[argh(switch)]
+    release: bool,
+}
+
+#[test]
+fn dispatches_to_subcommand() {
+    let parsed = WithSubcommand::from_args(&["cmd"], &["build", "--release"]).expect("should parse");
+    let Command::Build(build) = parsed.command;
+    assert!(build.release);
+}
+
+#[test]
+fn help_completion_recurses_into_subcommand_flags() {
+    let err = WithSubcommand::from_args(&["cmd"], &["--help-completion", "bash"])
+        .expect_err("always exits early");
+    assert!(err.output.contains("--release"));
+    assert!(err.output.contains("case \"${COMP_WORDS[1]}\" in"));
+
+    let err = WithSubcommand::from_args(&["cmd"], &["--help-completion", "zsh"])
+        .expect_err("always exits early");
+    assert!(err.output.contains("--release["));
+}
+
+#[test]
+fn commands_section_is_colorized_and_wrapped_like_options() {
+    std::env::set_var("ARGH_COLOR", "always");
+    std::env::set_var("COLUMNS", "40");
+    let err = WithSubcommand::from_args(&["cmd"], &["--help"]).expect_err("--help always exits early");
+    std::env::remove_var("ARGH_COLOR");
+    std::env::remove_var("COLUMNS");
+
+    // "build" is styled the same way option/flag names are: green SGR.
+    assert!(err.output.contains("\u{1b}[32mbuild\u{1b}[0m"));
+}
@@ -0,0 +1,625 @@
+// Copyright (c) 2020 Google LLC All rights reserved.
+// Use of this source code is governed by a BSD-style
+// license that can be found in the LICENSE file.
+
+//! Shared functionality between argh_derive and the argh runtime.
+//!
+//! This library is intended only for internal use by these two crates.
+
+use std::io::IsTerminal;
+
+pub const INDENT: &str = "  ";
+
+/// Marks the start and end of a styled span of text in generated help
+/// strings. `argh_derive` embeds these around headings, flag names, and
+/// placeholders (see [`style`]); [`colorize`] is the only thing that
+/// renders them, so the plain and colorized renderings are built from
+/// exactly the same structure.
+pub const STYLE_START: char = '\u{1}';
+/// See [`STYLE_START`].
+pub const STYLE_END: char = '\u{2}';
+
+/// Tag for a section heading, e.g. "Options:". See [`style`].
+pub const TAG_HEADING: char = 'H';
+/// Tag for a flag name or subcommand name, e.g. "-f, --force" or "build".
+/// See [`style`].
+pub const TAG_FLAG_NAME: char = 'F';
+/// Tag for a placeholder, e.g. "<file>". See [`style`].
+pub const TAG_PLACEHOLDER: char = 'P';
+
+/// Wraps `text` in the [`STYLE_START`]/[`STYLE_END`] control characters that
+/// mark it with `tag` for [`colorize`].
+pub fn style(tag: char, text: &str) -> String {
+    format!("{STYLE_START}{tag}{text}{STYLE_END}")
+}
+
+/// Whether to render [`STYLE_START`]/[`STYLE_END`]-delimited spans as ANSI
+/// SGR color codes. Mirrors clap's `ColorChoice`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Colorize only when stdout is a terminal and the user hasn't opted
+    /// out via `NO_COLOR`/`CLICOLOR=0`.
+    Auto,
+    /// Always colorize.
+    Always,
+    /// Never colorize.
+    Never,
+}
+
+impl ColorChoice {
+    fn should_colorize(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                if std::env::var_os("NO_COLOR").is_some() {
+                    return false;
+                }
+                if let Ok(clicolor) = std::env::var("CLICOLOR") {
+                    if clicolor == "0" {
+                        return false;
+                    }
+                }
+                std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// The `ColorChoice` a generated `--help` implementation should colorize
+/// with, read from the `ARGH_COLOR` environment variable (`"always"`,
+/// `"never"`, or `"auto"`, the default). This is how a caller picks
+/// `Always`/`Never` instead of the terminal-detecting `Auto` default,
+/// without argh needing a dedicated `--color` flag on every command.
+pub fn color_choice_from_env() -> ColorChoice {
+    match std::env::var("ARGH_COLOR").ok().as_deref() {
+        Some("always") => ColorChoice::Always,
+        Some("never") => ColorChoice::Never,
+        _ => ColorChoice::Auto,
+    }
+}
+
+/// Renders help text produced by `argh_derive`, translating each
+/// `STYLE_START`/`STYLE_END`-delimited span into ANSI SGR codes (or
+/// stripping the markers) depending on `choice`.
+pub fn colorize(text: &str, choice: ColorChoice) -> String {
+    let use_color = choice.should_colorize();
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != STYLE_START {
+            out.push(c);
+            continue;
+        }
+        let tag = chars.next().unwrap_or('\0');
+        let mut body = String::new();
+        for c2 in chars.by_ref() {
+            if c2 == STYLE_END {
+                break;
+            }
+            body.push(c2);
+        }
+        if use_color {
+            let (open, close) = sgr_for_tag(tag);
+            out.push_str(open);
+            out.push_str(&body);
+            out.push_str(close);
+        } else {
+            out.push_str(&body);
+        }
+    }
+    out
+}
+
+fn sgr_for_tag(tag: char) -> (&'static str, &'static str) {
+    match tag {
+        // Heading: bold + underline.
+        TAG_HEADING => ("\u{1b}[1;4m", "\u{1b}[0m"),
+        // FlagName: green.
+        TAG_FLAG_NAME => ("\u{1b}[32m", "\u{1b}[0m"),
+        // Placeholder: cyan.
+        TAG_PLACEHOLDER => ("\u{1b}[36m", "\u{1b}[0m"),
+        _ => ("", ""),
+    }
+}
+
+/// The visible (rendered) width of `s`: the same as `s.chars().count()`,
+/// except that `STYLE_START`/`STYLE_END`-delimited markers contribute only
+/// the width of their content, not the markers themselves.
+fn visible_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != STYLE_START {
+            width += 1;
+            continue;
+        }
+        chars.next(); // the style tag
+        for c2 in chars.by_ref() {
+            if c2 == STYLE_END {
+                break;
+            }
+            width += 1;
+        }
+    }
+    width
+}
+
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+const NAME_DESCRIPTION_GAP: usize = 2;
+
+/// The terminal width to wrap help text to: the `COLUMNS` environment
+/// variable if set to a positive integer, else a terminal-size syscall if
+/// stdout is a terminal, else [`DEFAULT_TERMINAL_WIDTH`].
+fn terminal_width() -> usize {
+    if let Some(width) = std::env::var("COLUMNS").ok().and_then(|c| c.trim().parse::<usize>().ok())
+    {
+        if width > 0 {
+            return width;
+        }
+    }
+    if let Some(width) = terminal_width_from_ioctl() {
+        return width;
+    }
+    DEFAULT_TERMINAL_WIDTH
+}
+
+#[cfg(unix)]
+fn terminal_width_from_ioctl() -> Option<usize> {
+    // SAFETY: `winsize` is a plain-old-data struct; `ioctl` only writes to
+    // it (it's a valid pointer to stack memory of the expected size), and
+    // we only read `ws_col` after checking the call succeeded.
+    unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) == 0 && size.ws_col > 0 {
+            Some(size.ws_col as usize)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn terminal_width_from_ioctl() -> Option<usize> {
+    None
+}
+
+/// Lays out a help section's `(name, description)` pairs against the
+/// current terminal width: computes the description column from the
+/// widest name, then greedily wraps each description to fit, preserving
+/// explicit `\n`s as forced breaks, collapsing runs of whitespace, and
+/// never breaking a single word wider than the available space.
+pub fn wrap_section(entries: &[(&str, &str)]) -> String {
+    let width = terminal_width();
+    let max_name_width = entries.iter().map(|(name, _)| visible_width(name)).max().unwrap_or(0);
+    let description_column = INDENT.len() + max_name_width + NAME_DESCRIPTION_GAP;
+    let available = width.saturating_sub(description_column).max(1);
+
+    let mut out = String::new();
+    for (name, description) in entries {
+        out.push('\n');
+        out.push_str(INDENT);
+        out.push_str(name);
+        if description.is_empty() {
+            continue;
+        }
+        let pad = description_column.saturating_sub(INDENT.len() + visible_width(name));
+        out.extend(std::iter::repeat_n(' ', pad));
+
+        let wrapped = wrap_words(description, available);
+        let mut lines = wrapped.split('\n');
+        if let Some(first) = lines.next() {
+            out.push_str(first);
+        }
+        for line in lines {
+            out.push('\n');
+            out.extend(std::iter::repeat_n(' ', description_column));
+            out.push_str(line);
+        }
+    }
+    out
+}
+
+/// Greedily packs whitespace-separated words from `text` into lines no
+/// wider than `available`, preserving explicit `\n`s as forced breaks and
+/// never breaking a single word wider than `available`.
+fn wrap_words(text: &str, available: usize) -> String {
+    let mut out = String::new();
+    let mut paragraphs = text.split('\n').peekable();
+    while let Some(paragraph) = paragraphs.next() {
+        let mut line_width = 0;
+        let mut words = paragraph.split_whitespace().peekable();
+        while let Some(word) = words.next() {
+            let word_width = word.chars().count();
+            if line_width == 0 {
+                out.push_str(word);
+                line_width = word_width;
+            } else if line_width + 1 + word_width <= available {
+                out.push(' ');
+                out.push_str(word);
+                line_width += 1 + word_width;
+            } else {
+                out.push('\n');
+                out.push_str(word);
+                line_width = word_width;
+            }
+            let _ = words.peek();
+        }
+        if paragraphs.peek().is_some() {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// A `#[derive(FromArgs)]` type's own flags, positionals, and (for a
+/// `#[argh(subcommand)]` field) the `(name, info)` of each subcommand it can
+/// dispatch to. Built entirely at compile time by `argh_derive` (mirroring
+/// `SubCommands::COMMANDS`), so [`generate_completion`] can walk the whole
+/// subcommand tree the same way `--help` does via `FromArgs::from_args`.
+#[derive(Clone, Copy)]
+pub struct CompletionInfo {
+    /// This type's own `--long` flags, including `--help`/`--help-json`/
+    /// `--help-completion`.
+    pub long_flags: &'static [&'static str],
+    /// This type's own `-s` short flags.
+    pub short_flags: &'static [&'static str],
+    /// This type's own positional argument names.
+    pub positional_names: &'static [&'static str],
+    /// `(long_flag, description, value_hint)` triples for this type's own
+    /// options, where `value_hint` matches `help_json`'s `"hint"` tags
+    /// (e.g. `"file_path"`).
+    pub option_hints: &'static [(&'static str, &'static str, &'static str)],
+    /// The subcommands this type can dispatch to, by name.
+    pub subcommands: &'static [(&'static str, &'static CompletionInfo)],
+}
+
+/// Generates a completion script for `shell` ("bash", "zsh", or "fish"),
+/// recursing into `info.subcommands` so the script offers a subcommand's own
+/// flags/positionals once that subcommand has been selected, the same way
+/// `--help` recurses into a subcommand's own `FromArgs::from_args`.
+pub fn generate_completion(shell: &str, command_name: &str, info: &CompletionInfo) -> String {
+    match shell {
+        "bash" => generate_bash_completion(command_name, info),
+        "zsh" => generate_zsh_completion(command_name, info),
+        "fish" => generate_fish_completion(command_name, info),
+        other => format!("# argh: unsupported shell for completion: {other}\n"),
+    }
+}
+
+/// Turns a command name into a valid shell function-name fragment.
+fn sanitize_fn_name(command_name: &str) -> String {
+    command_name.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect()
+}
+
+fn file_hinted_long_flags<'a>(
+    option_hints: &'a [(&'static str, &'static str, &'static str)],
+) -> Vec<&'a str> {
+    option_hints
+        .iter()
+        .filter(|(_, _, hint)| matches!(*hint, "file_path" | "dir_path" | "executable_path"))
+        .map(|(long, _, _)| *long)
+        .collect()
+}
+
+fn generate_bash_completion(command_name: &str, info: &CompletionInfo) -> String {
+    let fn_name = format!("_{}_complete", sanitize_fn_name(command_name));
+    let mut body = String::new();
+    write_bash_level(&mut body, info, 1, "    ");
+    format!(
+        "{fn_name}() {{\n    local cur prev\n    COMPREPLY=()\n    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n{body}}}\ncomplete -F {fn_name} {command_name}\n"
+    )
+}
+
+/// Emits the bash completion logic for one level of the subcommand tree:
+/// complete `info`'s own flags/positionals when `$COMP_CWORD` is exactly
+/// `word_index`, then (if `info` has subcommands) a `case` on
+/// `${COMP_WORDS[word_index]}` that recurses into the matched subcommand's
+/// own level at `word_index + 1`.
+fn write_bash_level(out: &mut String, info: &CompletionInfo, word_index: usize, indent: &str) {
+    let mut all_flags: Vec<&str> = Vec::new();
+    all_flags.extend_from_slice(info.long_flags);
+    all_flags.extend_from_slice(info.short_flags);
+    let flags_str = all_flags.join(" ");
+    let subcommand_names_str =
+        info.subcommands.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(" ");
+    let file_flags = file_hinted_long_flags(info.option_hints);
+
+    out.push_str(&format!("{indent}if [ \"$COMP_CWORD\" -eq {word_index} ]; then\n"));
+    if !file_flags.is_empty() {
+        out.push_str(&format!(
+            "{indent}    case \"$prev\" in\n{indent}        {})\n{indent}            COMPREPLY=( $(compgen -f -- \"$cur\") )\n{indent}            return 0\n{indent}            ;;\n{indent}    esac\n",
+            file_flags.join("|")
+        ));
+    }
+    out.push_str(&format!("{indent}    case \"$cur\" in\n"));
+    out.push_str(&format!(
+        "{indent}        -*)\n{indent}            COMPREPLY=( $(compgen -W \"{flags_str}\" -- \"$cur\") )\n{indent}            ;;\n"
+    ));
+    if !info.subcommands.is_empty() {
+        out.push_str(&format!(
+            "{indent}        *)\n{indent}            COMPREPLY=( $(compgen -W \"{subcommand_names_str}\" -- \"$cur\") )\n{indent}            ;;\n"
+        ));
+    } else if !info.positional_names.is_empty() {
+        out.push_str(&format!(
+            "{indent}        *)\n{indent}            COMPREPLY=( $(compgen -f -- \"$cur\") )\n{indent}            ;;\n"
+        ));
+    } else {
+        out.push_str(&format!("{indent}        *)\n{indent}            ;;\n"));
+    }
+    out.push_str(&format!("{indent}    esac\n{indent}    return 0\n{indent}fi\n"));
+
+    if !info.subcommands.is_empty() {
+        out.push_str(&format!("{indent}case \"${{COMP_WORDS[{word_index}]}}\" in\n"));
+        for (name, child) in info.subcommands {
+            out.push_str(&format!("{indent}    {name})\n"));
+            write_bash_level(out, child, word_index + 1, &format!("{indent}        "));
+            out.push_str(&format!("{indent}        ;;\n"));
+        }
+        out.push_str(&format!("{indent}esac\n"));
+    }
+}
+
+fn zsh_action_for_hint(hint: &str) -> &'static str {
+    match hint {
+        "file_path" => ":file:_files",
+        "dir_path" => ":dir:_files -/",
+        "executable_path" => ":executable:_command_names -e",
+        "hostname" => ":hostname:_hosts",
+        "username" => ":username:_users",
+        _ => "",
+    }
+}
+
+fn generate_zsh_completion(command_name: &str, info: &CompletionInfo) -> String {
+    let mut script = String::new();
+    script.push_str(&format!("#compdef {command_name}\n\n"));
+    write_zsh_function(&mut script, command_name, info);
+    script.push_str(&format!("_{}\n", sanitize_fn_name(command_name)));
+    script
+}
+
+/// Emits one `_<qualified_name>()` zsh completion function for `info`, plus
+/// (recursively) one per subcommand in `info.subcommands`, so selecting a
+/// subcommand dispatches into a function built from that subcommand's own
+/// `CompletionInfo` rather than the top-level one.
+fn write_zsh_function(out: &mut String, qualified_name: &str, info: &CompletionInfo) {
+    let mut specs: Vec<String> = Vec::new();
+    for long in info.long_flags {
+        let (description, hint) = info
+            .option_hints
+            .iter()
+            .find(|(l, _, _)| l == long)
+            .map(|(_, d, h)| (*d, *h))
+            .unwrap_or(("", "unknown"));
+        let action = zsh_action_for_hint(hint);
+        let escaped_description = description.replace('\'', "'\\''");
+        specs.push(format!("'{long}[{escaped_description}]{action}'"));
+    }
+    for name in info.positional_names {
+        specs.push(format!("'::{name}:_files'"));
+    }
+    if !info.subcommands.is_empty() {
+        specs.push("'*::command:->subcommand'".to_string());
+    }
+
+    out.push_str(&format!("_{}() {{\n", sanitize_fn_name(qualified_name)));
+    if specs.is_empty() {
+        out.push_str("    :\n");
+    } else {
+        out.push_str("    _arguments \\\n");
+        for (i, spec) in specs.iter().enumerate() {
+            let sep = if i + 1 == specs.len() { "\n" } else { " \\\n" };
+            out.push_str(&format!("        {spec}{sep}"));
+        }
+    }
+    if !info.subcommands.is_empty() {
+        let values =
+            info.subcommands.iter().map(|(n, _)| format!("'{n}'")).collect::<Vec<_>>().join(" ");
+        out.push_str("    case $state in\n        subcommand)\n");
+        out.push_str(&format!("            _values 'command' {values}\n"));
+        out.push_str("            case $words[1] in\n");
+        for (name, _) in info.subcommands {
+            let child_qualified = format!("{qualified_name}_{name}");
+            out.push_str(&format!(
+                "                {name}) _{} ;;\n",
+                sanitize_fn_name(&child_qualified)
+            ));
+        }
+        out.push_str("            esac\n            ;;\n    esac\n");
+    }
+    out.push_str("}\n\n");
+
+    for (name, child) in info.subcommands {
+        let child_qualified = format!("{qualified_name}_{name}");
+        write_zsh_function(out, &child_qualified, child);
+    }
+}
+
+fn generate_fish_completion(command_name: &str, info: &CompletionInfo) -> String {
+    let mut script = String::new();
+    write_fish_level(&mut script, command_name, info, &[]);
+    script
+}
+
+/// Emits fish `complete` lines for `info`'s own long flags/positionals (short
+/// flags are not emitted), each
+/// conditioned on `ancestors` (the subcommand names selected so far) via
+/// `__fish_seen_subcommand_from`, then recurses into each subcommand with
+/// that subcommand's name appended to `ancestors`.
+fn write_fish_level(out: &mut String, command_name: &str, info: &CompletionInfo, ancestors: &[&str]) {
+    let condition = if ancestors.is_empty() {
+        String::new()
+    } else {
+        format!(" -n \"__fish_seen_subcommand_from {}\"", ancestors.join(" "))
+    };
+    for long in info.long_flags {
+        let flag = long.trim_start_matches("--");
+        let hint = info.option_hints.iter().find(|(l, _, _)| l == long).map(|(_, _, h)| *h);
+        let mut line = format!("complete -c {command_name} -l {flag}{condition}");
+        if matches!(hint, Some("file_path") | Some("dir_path")) {
+            line.push_str(" -r -F");
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    if !info.positional_names.is_empty() && info.subcommands.is_empty() {
+        out.push_str(&format!("complete -c {command_name} -F{condition}\n"));
+    }
+    for (name, _) in info.subcommands {
+        out.push_str(&format!(
+            "complete -c {command_name} -n \"__fish_use_subcommand\"{condition} -a {name}\n"
+        ));
+    }
+    for (name, child) in info.subcommands {
+        let mut next_ancestors = ancestors.to_vec();
+        next_ancestors.push(name);
+        write_fish_level(out, command_name, child, &next_ancestors);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colorize_never_strips_markers() {
+        let text = format!("{STYLE_START}Hheading{STYLE_END} plain");
+        assert_eq!(colorize(&text, ColorChoice::Never), "heading plain");
+    }
+
+    #[test]
+    fn colorize_always_wraps_in_sgr() {
+        let text = format!("{STYLE_START}Hheading{STYLE_END}");
+        let colored = colorize(&text, ColorChoice::Always);
+        assert_eq!(colored, "\u{1b}[1;4mheading\u{1b}[0m");
+    }
+
+    #[test]
+    fn color_choice_from_env_reads_argh_color() {
+        std::env::set_var("ARGH_COLOR", "always");
+        assert_eq!(color_choice_from_env(), ColorChoice::Always);
+        std::env::set_var("ARGH_COLOR", "never");
+        assert_eq!(color_choice_from_env(), ColorChoice::Never);
+        std::env::remove_var("ARGH_COLOR");
+        assert_eq!(color_choice_from_env(), ColorChoice::Auto);
+    }
+
+    #[test]
+    fn wrap_section_aligns_description_column() {
+        let entries = [("-f, --force", "force it"), ("--verbose", "be verbose")];
+        std::env::set_var("COLUMNS", "80");
+        let out = wrap_section(&entries);
+        std::env::remove_var("COLUMNS");
+        for line in out.lines().filter(|l| !l.is_empty()) {
+            let description_start = line.find("force it").or_else(|| line.find("be verbose"));
+            if let Some(idx) = description_start {
+                assert_eq!(idx, INDENT.len() + "-f, --force".len() + NAME_DESCRIPTION_GAP);
+            }
+        }
+    }
+
+    #[test]
+    fn wrap_words_breaks_on_width_and_preserves_forced_newlines() {
+        let wrapped = wrap_words("one two three\nfour", 7);
+        assert_eq!(wrapped, "one two\nthree\nfour");
+    }
+
+    #[test]
+    fn wrap_words_never_breaks_an_overlong_word() {
+        let wrapped = wrap_words("a supercalifragilisticexpialidocious word", 10);
+        assert!(wrapped.lines().any(|l| l == "supercalifragilisticexpialidocious"));
+    }
+
+    const BUILD_INFO: CompletionInfo = CompletionInfo {
+        long_flags: &["--release", "--help"],
+        short_flags: &["-r"],
+        positional_names: &[],
+        option_hints: &[],
+        subcommands: &[],
+    };
+
+    const TOP_INFO: CompletionInfo = CompletionInfo {
+        long_flags: &["--force", "--help"],
+        short_flags: &["-f"],
+        positional_names: &[],
+        option_hints: &[("--force", "force it", "unknown")],
+        subcommands: &[("build", &BUILD_INFO)],
+    };
+
+    #[test]
+    fn generate_completion_bash_includes_flags_and_subcommands() {
+        let script = generate_completion("bash", "mycmd", &TOP_INFO);
+        assert!(script.contains("--force --help -f"));
+        assert!(script.contains("build"));
+        assert!(script.contains("complete -F _mycmd_complete mycmd"));
+    }
+
+    #[test]
+    fn generate_completion_bash_recurses_into_subcommand_flags() {
+        let script = generate_completion("bash", "mycmd", &TOP_INFO);
+        assert!(script.contains("case \"${COMP_WORDS[1]}\" in"));
+        assert!(script.contains("    build)"));
+        assert!(script.contains("--release --help -r"));
+    }
+
+    #[test]
+    fn generate_completion_bash_offers_file_completion_for_hinted_flags() {
+        let info = CompletionInfo {
+            long_flags: &["--path"],
+            short_flags: &[],
+            positional_names: &[],
+            option_hints: &[("--path", "a path", "file_path")],
+            subcommands: &[],
+        };
+        let script = generate_completion("bash", "mycmd", &info);
+        assert!(script.contains("compgen -f"));
+    }
+
+    #[test]
+    fn generate_completion_bash_offers_file_completion_for_positionals() {
+        let info = CompletionInfo {
+            long_flags: &["--help"],
+            short_flags: &[],
+            positional_names: &["path"],
+            option_hints: &[],
+            subcommands: &[],
+        };
+        let script = generate_completion("bash", "mycmd", &info);
+        assert!(script.contains("COMPREPLY=( $(compgen -f -- \"$cur\") )"));
+    }
+
+    #[test]
+    fn generate_completion_zsh_uses_value_hint_action() {
+        let info = CompletionInfo {
+            long_flags: &["--path"],
+            short_flags: &[],
+            positional_names: &[],
+            option_hints: &[("--path", "a path", "file_path")],
+            subcommands: &[],
+        };
+        let script = generate_completion("zsh", "mycmd", &info);
+        assert!(script.contains(":file:_files"));
+    }
+
+    #[test]
+    fn generate_completion_zsh_recurses_into_subcommand_function() {
+        let script = generate_completion("zsh", "mycmd", &TOP_INFO);
+        assert!(script.contains("_mycmd_build()"));
+        assert!(script.contains("build) _mycmd_build ;;"));
+        assert!(script.contains("'--release[]'"));
+    }
+
+    #[test]
+    fn generate_completion_unsupported_shell_is_a_comment() {
+        let info = CompletionInfo {
+            long_flags: &[],
+            short_flags: &[],
+            positional_names: &[],
+            option_hints: &[],
+            subcommands: &[],
+        };
+        let script = generate_completion("powershell", "mycmd", &info);
+        assert!(script.starts_with('#'));
+    }
+}